@@ -45,6 +45,10 @@ fn main() {
         builder.define("ROCKSDB_IOURING_PRESENT", Some("1"));
     }
 
+    if cfg!(feature = "fault-injection") {
+        builder.define("AUTOROCKS_FAULT_INJECTION", None);
+    }
+
     if target.contains("windows") {
         link("rpcrt4", false);
         link("shlwapi", false);
@@ -85,6 +89,9 @@ fn main() {
         fail_on_empty_directory("rocksdb");
         build_rocksdb();
     } else {
+        if cfg!(feature = "fault-injection") {
+            panic!("The fault-injection feature requires building the bundled RocksDB");
+        }
         let target = env::var("TARGET").unwrap();
         // according to https://github.com/alexcrichton/cc-rs/blob/master/src/lib.rs#L2189
         if target.contains("apple") || target.contains("freebsd") || target.contains("openbsd") {
@@ -321,6 +328,11 @@ fn build_rocksdb() {
 
     config.file("build_version.cc");
 
+    if cfg!(feature = "fault-injection") {
+        // Uses RocksDB internals, so it must be built with the same defines.
+        config.file("src/fault_injection.cc");
+    }
+
     config.cpp(true);
     config.flag_if_supported("-std=c++17");
     config.compile("librocksdb.a");
@@ -7,6 +7,10 @@
 #include <memory>
 #include "rocksdb/utilities/transaction_db.h"
 #include "rocksdb/utilities/options_util.h"
+#include "rocksdb/convenience.h"
+#include "rocksdb/merge_operator.h"
+#include "rocksdb/utilities/checkpoint.h"
+#include "fault_injection.h"
 
 using namespace std;
 using namespace rocksdb;
@@ -41,6 +45,58 @@ struct ReadOptionsWrapper : ReadOptions
     {
         snapshot = snapshot_;
     }
+
+    void set_iterate_lower_bound(const Slice *bound)
+    {
+        iterate_lower_bound = bound;
+    }
+
+    void set_iterate_upper_bound(const Slice *bound)
+    {
+        iterate_upper_bound = bound;
+    }
+};
+
+// A filesystem that can be told to fail writes or to forget unsynced data.
+//
+// The wrapper layout does not depend on AUTOROCKS_FAULT_INJECTION; without
+// it `env` stays null and the toggles do nothing.
+// Note: make sure it's Unpin.
+struct FaultInjectionWrapper
+{
+    shared_ptr<FileSystem> fs;
+    shared_ptr<Env> env;
+
+    FaultInjectionWrapper()
+    {
+#ifdef AUTOROCKS_FAULT_INJECTION
+        fs = new_fault_injection_fs();
+        env = NewCompositeEnv(fs);
+#endif
+    }
+
+    void set_filesystem_active(bool active) const
+    {
+#ifdef AUTOROCKS_FAULT_INJECTION
+        fault_injection_set_filesystem_active(fs.get(), active);
+#endif
+    }
+
+    void set_write_error(uint32_t seed, uint32_t one_in) const
+    {
+#ifdef AUTOROCKS_FAULT_INJECTION
+        fault_injection_set_write_error(fs.get(), seed, one_in);
+#endif
+    }
+
+    Status drop_unsynced_data() const
+    {
+#ifdef AUTOROCKS_FAULT_INJECTION
+        return fault_injection_drop_unsynced_data(fs.get());
+#else
+        return Status::NotSupported("fault injection is not enabled");
+#endif
+    }
 };
 
 struct DbOptionsWrapper
@@ -48,6 +104,9 @@ struct DbOptionsWrapper
     string path;
     DBOptions db_options;
     vector<ColumnFamilyDescriptor> cf_descriptors;
+    // Owns db_options.env if it is not the default one. The db wrappers
+    // keep a reference so it outlives the db.
+    shared_ptr<Env> env;
 
     DbOptionsWrapper(string path_)
         : DbOptionsWrapper(path_, 0)
@@ -82,6 +141,27 @@ struct DbOptionsWrapper
         }
     }
 
+    Status set_merge_operator(Slice id)
+    {
+        ConfigOptions config_options;
+        config_options.ignore_unsupported_options = false;
+        shared_ptr<MergeOperator> merge_operator;
+        auto status = MergeOperator::CreateFromString(config_options, id.ToString(), &merge_operator);
+        if (!status.ok())
+        {
+            return status;
+        }
+        if (!merge_operator)
+        {
+            return Status::InvalidArgument("unknown merge operator", id);
+        }
+        for (ColumnFamilyDescriptor &x : cf_descriptors)
+        {
+            x.options.merge_operator = merge_operator;
+        }
+        return status;
+    }
+
     Status load(Slice options_file)
     {
         auto columns = cf_descriptors.size();
@@ -90,10 +170,24 @@ struct DbOptionsWrapper
         {
             return status;
         }
+        if (env)
+        {
+            db_options.env = env.get();
+        }
         sort_and_complete_missing(columns);
         return status;
     }
 
+    void set_fault_injection(const FaultInjectionWrapper &fault_injection)
+    {
+        if (!fault_injection.env)
+        {
+            return;
+        }
+        env = fault_injection.env;
+        db_options.env = env.get();
+    }
+
     ColumnFamilyOptions *get_cf_option(size_t index)
     {
         return &cf_descriptors[index].options;
@@ -139,13 +233,23 @@ struct TransactionWrapper;
 // Note: make sure TransactionDBWrapper is Unpin.
 struct TransactionDBWrapper
 {
+    // Declared first so it is destroyed after the db.
+    shared_ptr<Env> env;
     unique_ptr<TransactionDB> db;
     std::vector<ColumnFamilyHandle *> cf_handles;
+    // Used by create_cf to re-create dropped column families.
+    std::vector<ColumnFamilyOptions> cf_options;
 
     Status open(
         const DbOptionsWrapper &options,
         const TransactionDBOptions &transaction_db_options)
     {
+        cf_options.clear();
+        for (const ColumnFamilyDescriptor &x : options.cf_descriptors)
+        {
+            cf_options.push_back(x.options);
+        }
+        env = options.env;
         TransactionDB *ptr;
         Status status = TransactionDB::Open(
             options.db_options,
@@ -242,6 +346,9 @@ struct TransactionDBWrapper
             return Status::OK();
         }
 
+        // Keep changes made by set_options.
+        auto options = db->GetOptions(cf);
+
         Status status = db->DropColumnFamily(cf);
         if (!status.ok())
         {
@@ -253,9 +360,24 @@ struct TransactionDBWrapper
             return status;
         }
         cf_handles[col] = nullptr;
+        cf_options[col] = options;
         return status;
     }
 
+    Status create_cf(size_t col)
+    {
+        if (col >= default_col())
+        {
+            return Status::InvalidArgument("column index out of range");
+        }
+        if (cf_handles[col])
+        {
+            return Status::OK();
+        }
+
+        return db->CreateColumnFamily(cf_options[col], to_string(col), &cf_handles[col]);
+    }
+
     Status get(const ReadOptions &options, ColumnFamilyHandle *cf, const Slice &key, PinnableSlice *slice) const
     {
         return db->Get(options, cf, key, slice);
@@ -271,11 +393,66 @@ struct TransactionDBWrapper
         return db->Delete(options, cf, key);
     }
 
+    Status merge(const WriteOptions &options, ColumnFamilyHandle *cf, const Slice &key, const Slice &value) const
+    {
+        return db->Merge(options, cf, key, value);
+    }
+
     bool get_int_property(ColumnFamilyHandle *cf, const Slice &property, uint64_t *value) const
     {
         return db->GetIntProperty(cf, property, value);
     }
 
+    unique_ptr<string> get_property(ColumnFamilyHandle *cf, const Slice &property) const
+    {
+        auto value = make_unique<string>();
+        if (!db->GetProperty(cf, property, value.get()))
+        {
+            return nullptr;
+        }
+        return value;
+    }
+
+    Status approximate_size(ColumnFamilyHandle *cf, const Slice &start, const Slice &end, uint64_t *size) const
+    {
+        SizeApproximationOptions options;
+        options.include_memtables = true;
+        Range range(start, end);
+        return db->GetApproximateSizes(options, cf, &range, 1, size);
+    }
+
+    Status verify_checksum() const
+    {
+        return db->VerifyChecksum();
+    }
+
+    void cancel_all_background_work(bool wait) const
+    {
+        CancelAllBackgroundWork(db.get(), wait);
+    }
+
+    Status flush(ColumnFamilyHandle *cf) const
+    {
+        return db->Flush(FlushOptions(), cf);
+    }
+
+    Status flush_wal(bool sync) const
+    {
+        return db->FlushWAL(sync);
+    }
+
+    Status create_checkpoint(Slice path) const
+    {
+        Checkpoint *ptr;
+        Status status = Checkpoint::Create(db.get(), &ptr);
+        if (!status.ok())
+        {
+            return status;
+        }
+        unique_ptr<Checkpoint> checkpoint(ptr);
+        return checkpoint->CreateCheckpoint(path.ToString());
+    }
+
     unique_ptr<Iterator> iter(const ReadOptions &options, ColumnFamilyHandle *cf) const
     {
         return unique_ptr<Iterator>(db->NewIterator(options, cf));
@@ -297,17 +474,25 @@ struct TransactionDBWrapper
     {
         db->ReleaseSnapshot(snapshot);
     }
+
+    uint64_t latest_sequence_number() const
+    {
+        return db->GetLatestSequenceNumber();
+    }
 };
 
 // Note: make sure ReadOnlyDbWrapper is Unpin.
 struct ReadOnlyDbWrapper
 {
+    // Declared first so it is destroyed after the db.
+    shared_ptr<Env> env;
     unique_ptr<DB> db;
     std::vector<ColumnFamilyHandle *> cf_handles;
 
     Status open(
         const DbOptionsWrapper &options)
     {
+        env = options.env;
         DB *ptr;
         Status status = DB::OpenForReadOnly(
             options.db_options,
@@ -353,6 +538,11 @@ struct ReadOnlyDbWrapper
     {
         return unique_ptr<Iterator>(db->NewIterator(options, cf));
     }
+
+    Status verify_checksum() const
+    {
+        return db->VerifyChecksum();
+    }
 };
 
 // Need this because autocxx cannot handle `shared_ptr<const Foo>`.
@@ -377,6 +567,11 @@ struct TransactionWrapper
         return tx->Get(options, cf, key, slice);
     }
 
+    Status get_for_update(const ReadOptions &options, ColumnFamilyHandle *cf, const Slice &key, PinnableSlice *slice, bool exclusive)
+    {
+        return tx->GetForUpdate(options, cf, key, slice, exclusive);
+    }
+
     Status put(ColumnFamilyHandle *cf, const Slice &key, const Slice &value)
     {
         return tx->Put(cf, key, value);
@@ -387,11 +582,21 @@ struct TransactionWrapper
         return tx->Delete(cf, key);
     }
 
+    Status merge(ColumnFamilyHandle *cf, const Slice &key, const Slice &value)
+    {
+        return tx->Merge(cf, key, value);
+    }
+
     const Snapshot *snapshot() const
     {
         return tx->GetSnapshot();
     }
 
+    void set_snapshot()
+    {
+        tx->SetSnapshot();
+    }
+
     SharedSnapshotWrapper timestamped_snapshot() const
     {
         return SharedSnapshotWrapper{tx->GetTimestampedSnapshot()};
@@ -407,6 +612,11 @@ struct TransactionWrapper
         return tx->Commit();
     }
 
+    uint64_t elapsed_time() const
+    {
+        return tx->GetElapsedTime();
+    }
+
     unique_ptr<Iterator> iter(const ReadOptions &options, ColumnFamilyHandle *cf) const
     {
         return unique_ptr<Iterator>(tx->GetIterator(options, cf));
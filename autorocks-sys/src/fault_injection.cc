@@ -0,0 +1,36 @@
+/*
+ * Copyright 2022, The Cozo Project Authors. Licensed under MIT/Apache-2.0/BSD-3-Clause.
+ */
+
+#include "fault_injection.h"
+#include "utilities/fault_injection_fs.h"
+
+using namespace std;
+using namespace rocksdb;
+
+shared_ptr<FileSystem> new_fault_injection_fs()
+{
+    return make_shared<FaultInjectionTestFS>(FileSystem::Default());
+}
+
+void fault_injection_set_filesystem_active(FileSystem *fs, bool active)
+{
+    static_cast<FaultInjectionTestFS *>(fs)->SetFilesystemActive(active, IOStatus::IOError("injected error"));
+}
+
+void fault_injection_set_write_error(FileSystem *fs, uint32_t seed, uint32_t one_in)
+{
+    auto fault_fs = static_cast<FaultInjectionTestFS *>(fs);
+    if (one_in == 0)
+    {
+        fault_fs->DisableWriteErrorInjection();
+        return;
+    }
+    fault_fs->SetRandomWriteError(seed, static_cast<int>(one_in), IOStatus::IOError("injected write error"), true, {});
+    fault_fs->EnableWriteErrorInjection();
+}
+
+Status fault_injection_drop_unsynced_data(FileSystem *fs)
+{
+    return static_cast<FaultInjectionTestFS *>(fs)->DropUnsyncedFileData();
+}
@@ -0,0 +1,22 @@
+/*
+ * Copyright 2022, The Cozo Project Authors. Licensed under MIT/Apache-2.0/BSD-3-Clause.
+ */
+
+#pragma once
+
+#include <memory>
+#include "rocksdb/file_system.h"
+#include "rocksdb/status.h"
+
+// Implemented in fault_injection.cc, which is compiled together with RocksDB
+// because it needs RocksDB's internal headers and build defines.
+//
+// Only declared here so db.h does not have to include those headers.
+
+std::shared_ptr<rocksdb::FileSystem> new_fault_injection_fs();
+
+void fault_injection_set_filesystem_active(rocksdb::FileSystem *fs, bool active);
+
+void fault_injection_set_write_error(rocksdb::FileSystem *fs, uint32_t seed, uint32_t one_in);
+
+rocksdb::Status fault_injection_drop_unsynced_data(rocksdb::FileSystem *fs);
@@ -35,6 +35,7 @@ autocxx::include_cpp! {
     generate!("SharedSnapshotWrapper")
     generate!("ReadOnlyDbWrapper")
     generate!("TransactionWrapper")
+    generate!("FaultInjectionWrapper")
 }
 
 pub use ffi::*;
@@ -43,6 +44,7 @@ impl Unpin for TransactionDBWrapper {}
 impl Unpin for ReadOnlyDbWrapper {}
 impl Unpin for TransactionWrapper {}
 impl Unpin for SharedSnapshotWrapper {}
+impl Unpin for FaultInjectionWrapper {}
 
 unsafe impl Send for TransactionDBWrapper {}
 unsafe impl Sync for TransactionDBWrapper {}
@@ -57,6 +59,9 @@ unsafe impl Sync for TransactionWrapper {}
 unsafe impl Send for SharedSnapshotWrapper {}
 unsafe impl Sync for SharedSnapshotWrapper {}
 
+unsafe impl Send for FaultInjectionWrapper {}
+unsafe impl Sync for FaultInjectionWrapper {}
+
 unsafe impl Send for rocksdb::WriteBatch {}
 // Sync because mutable methods take Pin<&mut Self>.
 unsafe impl Sync for rocksdb::WriteBatch {}
@@ -20,6 +20,49 @@ pub fn criterion_benchmark(c: &mut Criterion) {
         })
     });
 
+    #[cfg(feature = "autorocks")]
+    c.bench_function("autorocks write batch 1000 puts", |b| {
+        use autorocks::*;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = DbOptions::new(dir.path(), 1)
+            .create_if_missing(true)
+            .create_missing_column_families(true)
+            .open()
+            .unwrap();
+        let mut wb = db.new_write_batch();
+        b.iter(|| {
+            for i in 0u32..1000 {
+                wb.put(0, &i.to_be_bytes(), b"value").unwrap();
+            }
+            db.write(&mut wb).unwrap();
+            wb.clear();
+        })
+    });
+
+    #[cfg(feature = "autorocks")]
+    c.bench_function("autorocks db iter 1000", |b| {
+        use autorocks::*;
+
+        let dir = tempfile::tempdir().unwrap();
+        let db = DbOptions::new(dir.path(), 1)
+            .create_if_missing(true)
+            .create_missing_column_families(true)
+            .open()
+            .unwrap();
+        for i in 0u32..1000 {
+            db.put(0, &i.to_be_bytes(), b"value").unwrap();
+        }
+        b.iter(|| {
+            let mut iter = db.iter(0, Direction::Forward);
+            let mut n = 0;
+            while iter.advance() {
+                n += iter.value().unwrap().len();
+            }
+            assert_eq!(n, 5000);
+        })
+    });
+
     #[cfg(feature = "ckb-rocksdb")]
     c.bench_function("ckb-rocksdb db get", |b| {
         use ckb_rocksdb::{prelude::*, Options, DB};
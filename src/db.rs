@@ -1,4 +1,6 @@
-use std::{mem::MaybeUninit, os::unix::prelude::OsStrExt, path::Path, pin::Pin, sync::Arc};
+use std::{
+    mem::MaybeUninit, os::unix::prelude::OsStrExt, path::Path, pin::Pin, ptr::null, sync::Arc,
+};
 
 use autorocks_sys::{
     new_transaction_db_options, new_write_batch,
@@ -53,6 +55,26 @@ impl DbOptions {
         self
     }
 
+    /// Set a built-in merge operator for all column families, e.g.
+    /// `"uint64add"`, `"stringappend"` or `"max"`.
+    pub fn merge_operator(&mut self, id: &str) -> Result<&mut Self> {
+        moveit! {
+            let status = self.inner.as_mut().set_merge_operator(id.as_bytes().into());
+        }
+        into_result(&status)?;
+        Ok(self)
+    }
+
+    /// Route all file operations of dbs opened with these options through
+    /// `fault_injection`.
+    #[cfg(feature = "fault-injection")]
+    pub fn fault_injection(&mut self, fault_injection: &crate::FaultInjection) -> &mut Self {
+        self.inner
+            .as_mut()
+            .set_fault_injection(&fault_injection.inner);
+        self
+    }
+
     pub fn repair(&self) -> Result<()> {
         moveit! {
             let status = self.inner.repair();
@@ -109,11 +131,7 @@ impl TransactionDb {
     ///
     /// This only works when self is the sole instance of the db.
     pub fn clear_cf(&mut self, col: usize) -> Result<()> {
-        let inner = Arc::get_mut(&mut self.inner).ok_or_else(|| RocksDBStatusError {
-            msg: "Arc::get_mut failed".into(),
-            sub_code: autorocks_sys::rocksdb::Status_SubCode::kNone,
-            code: autorocks_sys::rocksdb::Status_Code::kBusy,
-        })?;
+        let inner = self.inner_mut()?;
         moveit! {
             let status = Pin::new(inner).clear_cf(col);
         }
@@ -122,17 +140,34 @@ impl TransactionDb {
 
     /// This only works when self is the sole instance of the db.
     pub fn drop_cf(&mut self, col: usize) -> Result<()> {
-        let inner = Arc::get_mut(&mut self.inner).ok_or_else(|| RocksDBStatusError {
-            msg: "Arc::get_mut failed".into(),
-            sub_code: autorocks_sys::rocksdb::Status_SubCode::kNone,
-            code: autorocks_sys::rocksdb::Status_Code::kBusy,
-        })?;
+        let inner = self.inner_mut()?;
         moveit! {
             let status = Pin::new(inner).drop_cf(col);
         }
         into_result(&status)
     }
 
+    /// Re-create a column family dropped by `drop_cf`, with the options it
+    /// had when it was dropped. Does nothing if the column family exists.
+    ///
+    /// This only works when self is the sole instance of the db.
+    pub fn create_cf(&mut self, col: usize) -> Result<()> {
+        let inner = self.inner_mut()?;
+        moveit! {
+            let status = Pin::new(inner).create_cf(col);
+        }
+        into_result(&status)
+    }
+
+    fn inner_mut(&mut self) -> Result<&mut TransactionDBWrapper> {
+        Arc::get_mut(&mut self.inner).ok_or_else(|| RocksDBStatusError {
+            msg: "Arc::get_mut failed".into(),
+            sub_code: autorocks_sys::rocksdb::Status_SubCode::kNone,
+            code: autorocks_sys::rocksdb::Status_Code::kBusy,
+            severity: autorocks_sys::rocksdb::Status_Severity::kNoError,
+        })
+    }
+
     pub fn put_with_options(
         &self,
         options: &WriteOptions,
@@ -169,6 +204,29 @@ impl TransactionDb {
         self.delete_with_options(&options, col, key)
     }
 
+    /// Requires a merge operator, see `DbOptions::merge_operator`.
+    pub fn merge(&self, col: usize, key: &[u8], value: &[u8]) -> Result<()> {
+        moveit! {
+            let options = WriteOptions::new();
+        }
+        self.merge_with_options(&options, col, key, value)
+    }
+
+    pub fn merge_with_options(
+        &self,
+        options: &WriteOptions,
+        col: usize,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<()> {
+        let cf = self.inner.get_cf(col);
+        assert!(!cf.is_null());
+        moveit! {
+            let status = unsafe { self.inner.merge(options, cf, &key.into(), &value.into()) };
+        }
+        into_result(&status)
+    }
+
     pub fn get<'b>(
         &self,
         col: usize,
@@ -212,6 +270,95 @@ impl TransactionDb {
         got.then_some(val)
     }
 
+    /// Get a string property such as `rocksdb.stats` or
+    /// `rocksdb.cfstats`.
+    pub fn get_property(&self, col: usize, property: &str) -> Option<String> {
+        let cf = self.inner.get_cf(col);
+        assert!(!cf.is_null());
+        let value = unsafe { self.inner.get_property(cf, &property.as_bytes().into()) };
+        value.as_ref().map(|v| v.to_string_lossy().into())
+    }
+
+    /// Approximate file system space used by keys in ["start", "end"),
+    /// including memtables.
+    ///
+    /// For an estimate of the number of keys in a whole column family, use
+    /// the `rocksdb.estimate-num-keys` int property.
+    pub fn approximate_size(&self, col: usize, start: &[u8], end: &[u8]) -> Result<u64> {
+        let cf = self.inner.get_cf(col);
+        assert!(!cf.is_null());
+        let mut size = 0;
+        moveit! {
+            let status = unsafe {
+                self.inner.approximate_size(cf, &start.into(), &end.into(), &mut size)
+            };
+        }
+        into_result(&status)?;
+        Ok(size)
+    }
+
+    /// Verify the checksums of all SST files.
+    pub fn verify_checksum(&self) -> Result<()> {
+        moveit! {
+            let status = self.inner.verify_checksum();
+        }
+        into_result(&status)
+    }
+
+    /// Stop background flushes and compactions, and if `wait` is true, wait
+    /// for running ones to finish.
+    ///
+    /// Intended for shutdown: flushes fail afterwards, and writes may stall
+    /// once the memtables are full.
+    ///
+    /// This only works when self is the sole instance of the db.
+    pub fn cancel_all_background_work(&mut self, wait: bool) -> Result<()> {
+        let inner = self.inner_mut()?;
+        inner.cancel_all_background_work(wait);
+        Ok(())
+    }
+
+    /// Flush the memtable of a column family to SST files and wait for it to
+    /// finish.
+    pub fn flush(&self, col: usize) -> Result<()> {
+        let cf = self.inner.get_cf(col);
+        assert!(!cf.is_null());
+        moveit! {
+            let status = unsafe { self.inner.flush(cf) };
+        }
+        into_result(&status)
+    }
+
+    /// Write buffered WAL data to the log file, and fsync it if `sync` is
+    /// true.
+    ///
+    /// To make individual writes durable, set `WriteOptions::sync` instead.
+    pub fn flush_wal(&self, sync: bool) -> Result<()> {
+        moveit! {
+            let status = self.inner.flush_wal(sync);
+        }
+        into_result(&status)
+    }
+
+    /// Create a consistent checkpoint of the db in `path`, which must not
+    /// exist yet.
+    ///
+    /// SST files are hard-linked if `path` is on the same filesystem. The
+    /// checkpoint is an independent db and can be opened with `DbOptions`.
+    pub fn create_checkpoint(&self, path: &Path) -> Result<()> {
+        moveit! {
+            let status = self.inner.create_checkpoint(path.as_os_str().as_bytes().into());
+        }
+        into_result(&status)
+    }
+
+    /// Sequence number of the most recent write. It increases with every
+    /// committed write, so comparing two readings tells whether the db has
+    /// been modified in between.
+    pub fn latest_sequence_number(&self) -> u64 {
+        self.inner.latest_sequence_number()
+    }
+
     pub fn snapshot(&self) -> Snapshot {
         Snapshot {
             inner: self.inner.get_snapshot(),
@@ -264,6 +411,26 @@ impl TransactionDb {
         unsafe { DbIterator::new(self.as_inner().iter(options, cf), dir) }
     }
 
+    /// Iterate over the keys in `lower..upper`.
+    ///
+    /// The bounds are also passed to RocksDB, which can then skip data
+    /// outside them. `seek` and `seek_for_prev` stay within the bounds too.
+    pub fn range_iter(
+        &self,
+        col: usize,
+        dir: Direction,
+        lower: &[u8],
+        upper: &[u8],
+    ) -> DbIterator<&'_ Self> {
+        let cf = self.inner.get_cf(col);
+        assert!(!cf.is_null());
+        unsafe {
+            DbIterator::range(null(), dir, lower, upper, |options| {
+                self.as_inner().iter(options, cf)
+            })
+        }
+    }
+
     pub fn new_write_batch(&self) -> WriteBatch {
         WriteBatch {
             inner: new_write_batch(),
@@ -400,6 +567,31 @@ impl ReadOnlyDb {
         unsafe { DbIterator::new(self.as_inner().iter(options, cf), dir) }
     }
 
+    /// Iterate over the keys in `lower..upper`.
+    pub fn range_iter(
+        &self,
+        col: usize,
+        dir: Direction,
+        lower: &[u8],
+        upper: &[u8],
+    ) -> DbIterator<&'_ Self> {
+        let cf = self.inner.get_cf(col);
+        assert!(!cf.is_null());
+        unsafe {
+            DbIterator::range(null(), dir, lower, upper, |options| {
+                self.as_inner().iter(options, cf)
+            })
+        }
+    }
+
+    /// Verify the checksums of all SST files.
+    pub fn verify_checksum(&self) -> Result<()> {
+        moveit! {
+            let status = self.inner.verify_checksum();
+        }
+        into_result(&status)
+    }
+
     pub fn as_inner(&self) -> &ReadOnlyDbWrapper {
         &self.inner
     }
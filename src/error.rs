@@ -1,11 +1,18 @@
 use std::fmt;
 
-use autorocks_sys::rocksdb::{Status, Status_Code, Status_SubCode};
+use autorocks_sys::rocksdb::{Status, Status_Code, Status_Severity, Status_SubCode};
 
 pub struct RocksDBStatusError {
     pub(crate) msg: String,
     pub code: Status_Code,
     pub sub_code: Status_SubCode,
+    pub severity: Status_Severity,
+}
+
+impl RocksDBStatusError {
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
 }
 
 impl fmt::Debug for RocksDBStatusError {
@@ -13,6 +20,8 @@ impl fmt::Debug for RocksDBStatusError {
         f.debug_struct("RocksDBStatusError")
             .field("msg", &self.msg)
             .field("code", &(self.code.clone() as u8))
+            .field("sub_code", &(self.sub_code.clone() as u8))
+            .field("severity", &(self.severity.clone() as u8))
             .finish()
     }
 }
@@ -34,6 +43,7 @@ pub fn into_result(status: &Status) -> Result<()> {
         Err(RocksDBStatusError {
             code: status.code(),
             sub_code: status.subcode(),
+            severity: status.severity(),
             msg: status.ToString().to_string_lossy().into(),
         })
     }
@@ -0,0 +1,50 @@
+use std::pin::Pin;
+
+use autorocks_sys::FaultInjectionWrapper;
+use moveit::{moveit, Emplace};
+
+use crate::{into_result, Result};
+
+/// A filesystem for testing how the db copes with IO errors and crashes.
+///
+/// Install it with `DbOptions::fault_injection`. The toggles take effect on
+/// every db opened with it, including ones opened earlier.
+pub struct FaultInjection {
+    pub(crate) inner: Pin<Box<FaultInjectionWrapper>>,
+}
+
+impl FaultInjection {
+    pub fn new() -> Self {
+        Self {
+            inner: Box::emplace(FaultInjectionWrapper::new()),
+        }
+    }
+
+    /// When inactive, all file operations fail with an IO error, as if the
+    /// machine had gone down.
+    pub fn set_filesystem_active(&self, active: bool) {
+        self.inner.set_filesystem_active(active);
+    }
+
+    /// Fail writes to files with an IO error with probability `1 / one_in`.
+    /// `one_in = 0` turns it off.
+    pub fn set_write_error(&self, seed: u32, one_in: u32) {
+        self.inner.set_write_error(seed, one_in);
+    }
+
+    /// Forget everything written to files since their last sync. Usually
+    /// called after closing the db with the filesystem inactive, to simulate
+    /// a crash.
+    pub fn drop_unsynced_data(&self) -> Result<()> {
+        moveit! {
+            let status = self.inner.drop_unsynced_data();
+        }
+        into_result(&status)
+    }
+}
+
+impl Default for FaultInjection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,9 +1,13 @@
 use std::{hint::unreachable_unchecked, marker::PhantomData, pin::Pin};
 
 use autocxx::prelude::UniquePtr;
-use autorocks_sys::rocksdb::Iterator;
+use autorocks_sys::{
+    rocksdb::{Iterator, ReadOptions, Slice, Snapshot},
+    ReadOptionsWrapper,
+};
+use moveit::moveit;
 
-use crate::slice::as_rust_slice1;
+use crate::{into_result, slice::as_rust_slice1, Result};
 
 pub enum Direction {
     Forward,
@@ -14,23 +18,83 @@ pub struct DbIterator<T> {
     pub(crate) inner: UniquePtr<Iterator>,
     pub(crate) just_seeked: bool,
     pub(crate) direction: Direction,
+    // Must be dropped after inner.
+    pub(crate) bounds: Option<Box<IterBounds>>,
     pub(crate) phantom: PhantomData<T>,
 }
 
+/// Bounds of a `range_iter`. The read options of the iterator point into
+/// it, so it is boxed and kept alive by the `DbIterator`.
+pub(crate) struct IterBounds {
+    lower: Box<[u8]>,
+    upper: Box<[u8]>,
+    lower_slice: Slice,
+    upper_slice: Slice,
+}
+
+impl IterBounds {
+    pub(crate) fn new(lower: &[u8], upper: &[u8]) -> Box<Self> {
+        let lower: Box<[u8]> = lower.into();
+        let upper: Box<[u8]> = upper.into();
+        Box::new(Self {
+            lower_slice: (&lower[..]).into(),
+            upper_slice: (&upper[..]).into(),
+            lower,
+            upper,
+        })
+    }
+
+    /// Safety: self must outlive iterators created with options.
+    pub(crate) unsafe fn apply(&self, mut options: Pin<&mut ReadOptionsWrapper>) {
+        options.as_mut().set_iterate_lower_bound(&self.lower_slice);
+        options.set_iterate_upper_bound(&self.upper_slice);
+    }
+}
+
 impl<T> DbIterator<T> {
     /// Safety: inner must NOT be null.
-    pub(crate) unsafe fn new(mut inner: UniquePtr<Iterator>, direction: Direction) -> Self {
-        let ptr = unwrap_unchecked(inner.as_mut());
-        match direction {
-            Direction::Forward => ptr.SeekToFirst(),
-            Direction::Backward => ptr.SeekToLast(),
+    pub(crate) unsafe fn new(inner: UniquePtr<Iterator>, direction: Direction) -> Self {
+        Self::with_bounds(inner, direction, None)
+    }
+
+    /// Create an iterator over `lower..upper` with `iter`, which gets read
+    /// options with the bounds and `snapshot` (if not null) set.
+    ///
+    /// Safety: iter must NOT return null.
+    pub(crate) unsafe fn range(
+        snapshot: *const Snapshot,
+        direction: Direction,
+        lower: &[u8],
+        upper: &[u8],
+        iter: impl FnOnce(&ReadOptions) -> UniquePtr<Iterator>,
+    ) -> Self {
+        let bounds = IterBounds::new(lower, upper);
+        moveit! {
+            let mut options = ReadOptionsWrapper::new();
         }
-        Self {
+        options.as_mut().set_snapshot(snapshot);
+        bounds.apply(options.as_mut());
+        let inner = iter((*options).as_ref());
+        Self::with_bounds(inner, direction, Some(bounds))
+    }
+
+    unsafe fn with_bounds(
+        inner: UniquePtr<Iterator>,
+        direction: Direction,
+        bounds: Option<Box<IterBounds>>,
+    ) -> Self {
+        let mut iter = Self {
             inner,
             just_seeked: true,
             direction,
+            bounds,
             phantom: PhantomData,
+        };
+        match iter.direction {
+            Direction::Forward => iter.seek_to_first(),
+            Direction::Backward => iter.seek_to_last(),
         }
+        iter
     }
 
     pub fn as_inner(&self) -> &Iterator {
@@ -42,17 +106,86 @@ impl<T> DbIterator<T> {
     }
 
     pub fn seek(&mut self, key: &[u8]) {
-        self.as_inner_mut().Seek(&key.into());
+        let target: Slice = match &self.bounds {
+            Some(bounds) if key < &bounds.lower[..] => (&bounds.lower[..]).into(),
+            _ => key.into(),
+        };
+        self.as_inner_mut().Seek(&target);
         self.just_seeked = true;
     }
 
     pub fn seek_for_prev(&mut self, key: &[u8]) {
-        self.as_inner_mut().SeekForPrev(&key.into());
+        let past_upper = matches!(&self.bounds, Some(bounds) if key >= &bounds.upper[..]);
+        if past_upper {
+            self.seek_to_last();
+        } else {
+            self.as_inner_mut().SeekForPrev(&key.into());
+        }
         self.just_seeked = true;
     }
 
+    fn seek_to_first(&mut self) {
+        let lower: Option<Slice> = self.bounds.as_ref().map(|b| (&b.lower[..]).into());
+        match lower {
+            Some(lower) => self.as_inner_mut().Seek(&lower),
+            None => self.as_inner_mut().SeekToFirst(),
+        }
+    }
+
+    fn seek_to_last(&mut self) {
+        let upper: Option<Slice> = self.bounds.as_ref().map(|b| (&b.upper[..]).into());
+        match upper {
+            Some(upper) => {
+                self.as_inner_mut().SeekForPrev(&upper);
+                // The upper bound itself is excluded.
+                if self.as_inner().Valid() && !self.in_bounds() {
+                    self.as_inner_mut().Prev();
+                }
+            }
+            None => self.as_inner_mut().SeekToLast(),
+        }
+    }
+
+    /// Move to the next entry (or stay at the current one right after
+    /// creation or a seek), and return whether it is valid.
+    ///
+    /// Combined with `key` and `value` this scans without the allocations
+    /// done by `Iterator::next`.
+    pub fn advance(&mut self) -> bool {
+        if !self.just_seeked {
+            match self.direction {
+                Direction::Backward => self.as_inner_mut().Prev(),
+                Direction::Forward => self.as_inner_mut().Next(),
+            }
+        } else {
+            self.just_seeked = false;
+        }
+        self.valid()
+    }
+
     pub fn valid(&self) -> bool {
-        self.as_inner().Valid()
+        self.as_inner().Valid() && self.in_bounds()
+    }
+
+    // RocksDB enforces the bounds for db iterators, but not for the
+    // transaction's own writes in transaction iterators.
+    fn in_bounds(&self) -> bool {
+        match &self.bounds {
+            None => true,
+            Some(bounds) => {
+                let key = unsafe { as_rust_slice1(self.as_inner().key()) };
+                key >= &bounds.lower[..] && key < &bounds.upper[..]
+            }
+        }
+    }
+
+    /// The iterator becomes invalid both when it reaches the end and when it
+    /// encounters an error (e.g. corruption). Use this to tell them apart.
+    pub fn status(&self) -> Result<()> {
+        moveit! {
+            let status = self.as_inner().status();
+        }
+        into_result(&status)
     }
 
     pub fn key(&self) -> Option<&[u8]> {
@@ -76,19 +209,13 @@ impl<T> core::iter::Iterator for DbIterator<T> {
     type Item = (Box<[u8]>, Box<[u8]>);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if !self.just_seeked {
-            match self.direction {
-                Direction::Backward => self.as_inner_mut().Prev(),
-                Direction::Forward => self.as_inner_mut().Next(),
-            }
-        } else {
-            self.just_seeked = false;
-        }
-        if self.as_inner().Valid() {
-            let v = (
-                unsafe { unwrap_unchecked(self.key()) }.into(),
-                unsafe { unwrap_unchecked(self.value()) }.into(),
-            );
+        if self.advance() {
+            let v = unsafe {
+                (
+                    as_rust_slice1(self.as_inner().key()).into(),
+                    as_rust_slice1(self.as_inner().value()).into(),
+                )
+            };
             Some(v)
         } else {
             None
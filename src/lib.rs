@@ -3,6 +3,8 @@ pub extern crate moveit;
 
 mod db;
 mod error;
+#[cfg(feature = "fault-injection")]
+mod fault_injection;
 mod iter;
 mod slice;
 mod snapshot;
@@ -11,6 +13,8 @@ mod write_batch;
 
 pub use db::*;
 pub use error::*;
+#[cfg(feature = "fault-injection")]
+pub use fault_injection::*;
 pub use iter::*;
 pub use snapshot::*;
 pub use transaction::*;
@@ -41,9 +41,27 @@ impl Snapshot {
             inner: iter.inner,
             just_seeked: iter.just_seeked,
             direction: iter.direction,
+            bounds: iter.bounds,
             phantom: PhantomData,
         }
     }
+
+    /// Iterate over the keys in `lower..upper`.
+    pub fn range_iter(
+        &self,
+        col: usize,
+        dir: Direction,
+        lower: &[u8],
+        upper: &[u8],
+    ) -> DbIterator<&'_ Self> {
+        let cf = self.db.as_inner().get_cf(col);
+        assert!(!cf.is_null());
+        unsafe {
+            DbIterator::range(self.inner, dir, lower, upper, |options| {
+                self.db.as_inner().iter(options, cf)
+            })
+        }
+    }
 }
 
 impl Drop for Snapshot {
@@ -87,9 +105,27 @@ impl SharedSnapshot {
             inner: iter.inner,
             just_seeked: iter.just_seeked,
             direction: iter.direction,
+            bounds: iter.bounds,
             phantom: PhantomData,
         }
     }
+
+    /// Iterate over the keys in `lower..upper`.
+    pub fn range_iter(
+        &self,
+        col: usize,
+        dir: Direction,
+        lower: &[u8],
+        upper: &[u8],
+    ) -> DbIterator<&'_ Self> {
+        let cf = self.db.as_inner().get_cf(col);
+        assert!(!cf.is_null());
+        unsafe {
+            DbIterator::range(self.inner.get(), dir, lower, upper, |options| {
+                self.db.as_inner().iter(options, cf)
+            })
+        }
+    }
 }
 
 pub struct SnapshotRef<'a> {
@@ -128,7 +164,25 @@ impl<'a> SnapshotRef<'a> {
             inner: iter.inner,
             just_seeked: iter.just_seeked,
             direction: iter.direction,
+            bounds: iter.bounds,
             phantom: PhantomData,
         }
     }
+
+    /// Iterate over the keys in `lower..upper`.
+    pub fn range_iter(
+        &self,
+        col: usize,
+        dir: Direction,
+        lower: &[u8],
+        upper: &[u8],
+    ) -> DbIterator<&'_ Self> {
+        let cf = self.tx.db.as_inner().get_cf(col);
+        assert!(!cf.is_null());
+        unsafe {
+            DbIterator::range(self.inner, dir, lower, upper, |options| {
+                self.tx.as_inner().iter(options, cf)
+            })
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use std::{mem::MaybeUninit, pin::Pin};
+use std::{mem::MaybeUninit, pin::Pin, ptr::null, time::Duration};
 
 use autorocks_sys::{
     rocksdb::{PinnableSlice, ReadOptions},
@@ -35,6 +35,15 @@ impl Transaction {
         into_result(&status)
     }
 
+    pub fn merge(&mut self, col: usize, key: &[u8], value: &[u8]) -> Result<()> {
+        let cf = self.db.as_inner().get_cf(col);
+        assert!(!cf.is_null());
+        moveit! {
+            let status = unsafe { self.as_inner_mut().merge(cf, &key.into(), &value.into()) };
+        }
+        into_result(&status)
+    }
+
     pub fn get<'b>(
         &self,
         col: usize,
@@ -67,6 +76,46 @@ impl Transaction {
         Ok(Some(as_rust_slice(slice)))
     }
 
+    /// Read a key and lock it until the transaction commits or rolls back.
+    ///
+    /// With `exclusive = false` other transactions may still read the key
+    /// for update, but none can write it.
+    pub fn get_for_update<'b>(
+        &mut self,
+        col: usize,
+        key: &[u8],
+        exclusive: bool,
+        buf: Pin<&'b mut PinnableSlice>,
+    ) -> Result<Option<&'b [u8]>> {
+        moveit! {
+            let options = ReadOptions::new();
+        }
+        self.get_for_update_with_options(&options, col, key, exclusive, buf)
+    }
+
+    pub fn get_for_update_with_options<'b>(
+        &mut self,
+        options: &ReadOptions,
+        col: usize,
+        key: &[u8],
+        exclusive: bool,
+        buf: Pin<&'b mut PinnableSlice>,
+    ) -> Result<Option<&'b [u8]>> {
+        let slice = unsafe { buf.get_unchecked_mut() };
+        let cf = self.db.as_inner().get_cf(col);
+        assert!(!cf.is_null());
+        moveit! {
+            let status = unsafe {
+                self.as_inner_mut().get_for_update(options, cf, &key.into(), slice, exclusive)
+            };
+        }
+        if status.IsNotFound() {
+            return Ok(None);
+        }
+        into_result(&status)?;
+        Ok(Some(as_rust_slice(slice)))
+    }
+
     /// # Panics
     ///
     /// If there are no snapshot set for this transaction.
@@ -78,6 +127,16 @@ impl Transaction {
         }
     }
 
+    /// Replace the snapshot of the transaction with a new one taken now.
+    ///
+    /// Reads through `snapshot` will then see changes committed by others in
+    /// the meantime, and later writes are checked for conflicts against the
+    /// new snapshot. The transaction's own writes are visible to its reads
+    /// either way.
+    pub fn set_snapshot(&mut self) {
+        self.as_inner_mut().set_snapshot();
+    }
+
     /// Similar to `snapshot`, but the returned snapshot can outlive the
     /// transaction.
     ///
@@ -117,6 +176,23 @@ impl Transaction {
         unsafe { DbIterator::new(self.as_inner().iter(options, cf), dir) }
     }
 
+    /// Iterate over the keys in `lower..upper`.
+    pub fn range_iter(
+        &self,
+        col: usize,
+        dir: Direction,
+        lower: &[u8],
+        upper: &[u8],
+    ) -> DbIterator<&'_ Self> {
+        let cf = self.db.as_inner().get_cf(col);
+        assert!(!cf.is_null());
+        unsafe {
+            DbIterator::range(null(), dir, lower, upper, |options| {
+                self.as_inner().iter(options, cf)
+            })
+        }
+    }
+
     pub fn rollback(&mut self) -> Result<()> {
         moveit! {
             let status = self.as_inner_mut().rollback();
@@ -131,7 +207,16 @@ impl Transaction {
         into_result(&status)
     }
 
-    fn as_inner(&self) -> &TransactionWrapper {
+    /// Time elapsed since the transaction began.
+    ///
+    /// Useful for finding idle transactions that pin a snapshot. To make
+    /// RocksDB reject such transactions at commit, set
+    /// `TransactionOptions::expiration`.
+    pub fn elapsed_time(&self) -> Duration {
+        Duration::from_millis(self.as_inner().elapsed_time())
+    }
+
+    pub(crate) fn as_inner(&self) -> &TransactionWrapper {
         &self.inner
     }
 
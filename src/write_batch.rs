@@ -29,6 +29,15 @@ impl WriteBatch {
         into_result(&status)
     }
 
+    pub fn merge(&mut self, col: usize, key: &[u8], value: &[u8]) -> Result<()> {
+        let cf = self.db.as_inner().get_cf(col);
+        assert!(!cf.is_null());
+        moveit! {
+            let status = unsafe { self.as_inner_mut().Merge(cf, &key.into(), &value.into()) };
+        }
+        into_result(&status)
+    }
+
     /// Delete entries in the range of ["begin_key", "end_key").
     pub fn delete_range(&mut self, col: usize, begin_key: &[u8], end_key: &[u8]) -> Result<()> {
         let cf = self.db.as_inner().get_cf(col);
@@ -39,6 +48,25 @@ impl WriteBatch {
         into_result(&status)
     }
 
+    /// Number of updates in the batch.
+    pub fn len(&self) -> usize {
+        self.as_inner().Count() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Size of the batch's serialized representation in bytes.
+    pub fn data_size(&self) -> usize {
+        self.as_inner().GetDataSize()
+    }
+
+    /// Remove all updates, so the batch can be reused.
+    pub fn clear(&mut self) {
+        self.as_inner_mut().Clear();
+    }
+
     pub fn as_inner_mut(&mut self) -> Pin<&mut autorocks_sys::rocksdb::WriteBatch> {
         match self.inner.as_mut() {
             Some(x) => x,
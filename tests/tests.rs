@@ -1,6 +1,10 @@
 use autorocks::*;
-use autorocks_sys::rocksdb::{CompressionType, PinnableSlice, Status_Code};
+use autorocks_sys::rocksdb::{
+    CompressionType, PinnableSlice, Status_Code, Status_Severity, Status_SubCode,
+    TransactionOptions, WriteOptions,
+};
 use moveit::moveit;
+use std::path::Path;
 use tempfile::{tempdir, TempDir};
 
 fn open_temp(columns: usize) -> (TransactionDb, TempDir) {
@@ -15,6 +19,18 @@ fn open_temp(columns: usize) -> (TransactionDb, TempDir) {
     )
 }
 
+/// Flip the first byte of the (only) SST file in `dir`.
+fn corrupt_sst(dir: &Path) {
+    let sst = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "sst"))
+        .unwrap();
+    let mut data = std::fs::read(&sst).unwrap();
+    data[0] ^= 0xff;
+    std::fs::write(&sst, data).unwrap();
+}
+
 #[test]
 fn test_db_open_put_get_delete_drop_cf_int_property() {
     let (mut db, _dir) = open_temp(1);
@@ -42,6 +58,57 @@ fn test_db_open_put_get_delete_drop_cf_int_property() {
     assert!(size > 0);
 }
 
+#[test]
+fn test_get_property() {
+    let (db, _dir) = open_temp(1);
+    let stats = db.get_property(0, "rocksdb.stats").unwrap();
+    assert!(!stats.is_empty());
+    assert!(db.get_property(0, "rocksdb.no-such-property").is_none());
+}
+
+#[test]
+fn test_verify_checksum() {
+    let (db, dir) = open_temp(1);
+    db.put(0, b"key", b"value").unwrap();
+    db.flush(0).unwrap();
+    db.verify_checksum().unwrap();
+
+    corrupt_sst(dir.path());
+    let err = db.verify_checksum().unwrap_err();
+    assert!(err.code == Status_Code::kCorruption);
+}
+
+#[test]
+fn test_flush() {
+    let (db, _dir) = open_temp(1);
+    db.put(0, b"key", b"value").unwrap();
+    db.flush_wal(true).unwrap();
+    db.flush(0).unwrap();
+    let files = db
+        .get_int_property(0, "rocksdb.num-files-at-level0")
+        .unwrap();
+    assert_eq!(files, 1);
+}
+
+#[test]
+fn test_approximate_size() {
+    let (db, _dir) = open_temp(1);
+    for i in 0u32..1000 {
+        db.put(0, &i.to_be_bytes(), b"value").unwrap();
+    }
+    db.flush(0).unwrap();
+    let size = db
+        .approximate_size(0, &0u32.to_be_bytes(), &u32::MAX.to_be_bytes())
+        .unwrap();
+    assert!(size > 0);
+
+    // No keys after the last one.
+    let size = db
+        .approximate_size(0, &u32::MAX.to_be_bytes(), &[0xff; 5])
+        .unwrap();
+    assert_eq!(size, 0);
+}
+
 #[test]
 fn test_db_set_options() {
     let (db, _dir) = open_temp(1);
@@ -63,6 +130,92 @@ fn test_read_only_db() {
     assert_eq!(v.unwrap(), b"value");
 }
 
+#[test]
+fn test_read_only_db_verify_checksum() {
+    let (db, dir) = open_temp(1);
+    db.put(0, b"key", b"value").unwrap();
+    db.flush(0).unwrap();
+    drop(db);
+
+    let rdb = DbOptions::new(dir.path(), 1).open_read_only().unwrap();
+    rdb.verify_checksum().unwrap();
+    corrupt_sst(dir.path());
+    let err = rdb.verify_checksum().unwrap_err();
+    assert!(err.code == Status_Code::kCorruption);
+}
+
+#[test]
+fn test_repair() {
+    let (db, dir) = open_temp(1);
+    db.put(0, b"key", b"value").unwrap();
+    // Repair finds column families through their SST files.
+    db.flush(0).unwrap();
+    drop(db);
+
+    for entry in std::fs::read_dir(dir.path()).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_str().unwrap();
+        if name == "CURRENT" || name.starts_with("MANIFEST-") {
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+    let options = DbOptions::new(dir.path(), 1);
+    assert!(options.open().is_err());
+    options.repair().unwrap();
+    let db = options.open().unwrap();
+    moveit! {
+        let mut slice = PinnableSlice::new();
+    }
+    let v = db.get(0, b"key", slice.as_mut()).unwrap();
+    assert_eq!(v.unwrap(), b"value");
+}
+
+#[test]
+fn test_cancel_all_background_work() {
+    let (mut db, dir) = open_temp(1);
+    db.put(0, b"key", b"value").unwrap();
+
+    let db1 = db.clone();
+    assert!(db.cancel_all_background_work(true).is_err());
+    drop(db1);
+
+    db.cancel_all_background_work(true).unwrap();
+    assert!(db.flush(0).is_err());
+    drop(db);
+
+    let db = DbOptions::new(dir.path(), 1).open().unwrap();
+    moveit! {
+        let mut slice = PinnableSlice::new();
+    }
+    let v = db.get(0, b"key", slice.as_mut()).unwrap();
+    assert_eq!(v.unwrap(), b"value");
+}
+
+#[test]
+fn test_checkpoint() {
+    let (db, _dir) = open_temp(2);
+    db.put(0, b"key", b"value").unwrap();
+    // The checkpoint directory must not exist yet.
+    let checkpoint_dir = tempdir().unwrap();
+    let checkpoint_path = checkpoint_dir.path().join("checkpoint");
+    db.create_checkpoint(&checkpoint_path).unwrap();
+    db.put(0, b"key", b"value1").unwrap();
+    db.put(0, b"key1", b"value1").unwrap();
+    db.put(1, b"key", b"value1").unwrap();
+
+    let rdb = DbOptions::new(&checkpoint_path, 2)
+        .open_read_only()
+        .unwrap();
+    moveit! {
+        let mut slice = PinnableSlice::new();
+    }
+    let v = rdb.get(0, b"key", slice.as_mut()).unwrap();
+    assert_eq!(v.unwrap(), b"value");
+    assert!(rdb.get(0, b"key1", slice.as_mut()).unwrap().is_none());
+    assert!(rdb.get(1, b"key", slice.as_mut()).unwrap().is_none());
+    assert_eq!(rdb.iter(1, Direction::Forward).count(), 0);
+}
+
 #[cfg(feature = "snappy")]
 #[test]
 fn test_db_open_snappy() {
@@ -76,6 +229,60 @@ fn test_db_open_snappy() {
     db.put(0, b"key", b"value").unwrap();
 }
 
+#[cfg(feature = "fault-injection")]
+#[test]
+fn test_fault_injection_write_error() {
+    let dir = tempdir().unwrap();
+    let fault_injection = FaultInjection::new();
+    let db = DbOptions::new(dir.path(), 1)
+        .create_if_missing(true)
+        .create_missing_column_families(true)
+        .fault_injection(&fault_injection)
+        .open()
+        .unwrap();
+    db.put(0, b"key", b"value").unwrap();
+
+    fault_injection.set_write_error(0, 1);
+    let err = db.put(0, b"key1", b"value1").unwrap_err();
+    assert!(err.code == Status_Code::kIOError);
+}
+
+#[cfg(feature = "fault-injection")]
+#[test]
+fn test_fault_injection_crash_recovery() {
+    let dir = tempdir().unwrap();
+    let fault_injection = FaultInjection::new();
+    let db = DbOptions::new(dir.path(), 1)
+        .create_if_missing(true)
+        .create_missing_column_families(true)
+        .fault_injection(&fault_injection)
+        .open()
+        .unwrap();
+    moveit! {
+        let mut write_options = WriteOptions::new();
+        let transaction_options = TransactionOptions::new();
+    }
+    write_options.sync = true;
+    let mut tx = db.begin_transaction_with_options(&write_options, &transaction_options);
+    tx.put(0, b"key", b"value").unwrap();
+    tx.commit().unwrap();
+    db.put(0, b"key1", b"value1").unwrap();
+
+    // Crash: nothing reaches the disk any more, and unsynced writes are lost.
+    fault_injection.set_filesystem_active(false);
+    drop(db);
+    fault_injection.drop_unsynced_data().unwrap();
+    fault_injection.set_filesystem_active(true);
+
+    let db = DbOptions::new(dir.path(), 1).open().unwrap();
+    moveit! {
+        let mut slice = PinnableSlice::new();
+    }
+    let v = db.get(0, b"key", slice.as_mut()).unwrap();
+    assert_eq!(v.unwrap(), b"value");
+    assert!(db.get(0, b"key1", slice.as_mut()).unwrap().is_none());
+}
+
 #[test]
 fn test_snapshot() {
     let (db, _dir) = open_temp(1);
@@ -126,6 +333,77 @@ fn test_tx_and_tx_snapshot() {
     assert_eq!(v, b"value");
 }
 
+#[test]
+fn test_status_error() {
+    let (db, _dir) = open_temp(1);
+    db.put(0, b"key", b"value").unwrap();
+    moveit! {
+        let mut slice = PinnableSlice::new();
+        let write_options = WriteOptions::new();
+        let mut transaction_options = TransactionOptions::new();
+    }
+    transaction_options.lock_timeout = 0;
+
+    let mut tx = db.begin_transaction();
+    tx.get_for_update(0, b"key", true, slice.as_mut()).unwrap();
+    let mut tx1 = db.begin_transaction_with_options(&write_options, &transaction_options);
+    let err = tx1
+        .get_for_update(0, b"key", true, slice.as_mut())
+        .unwrap_err();
+    assert!(err.code == Status_Code::kTimedOut);
+    assert!(err.sub_code == Status_SubCode::kLockTimeout);
+    assert!(err.severity == Status_Severity::kNoError);
+    assert_eq!(
+        err.msg(),
+        "Operation timed out: Timeout waiting to lock key"
+    );
+}
+
+#[test]
+fn test_tx_set_snapshot() {
+    let (db, _dir) = open_temp(1);
+    db.put(0, b"key", b"value").unwrap();
+    moveit! {
+        let mut slice = PinnableSlice::new();
+    }
+    let mut tx = db.begin_transaction();
+    db.put(0, b"key", b"value1").unwrap();
+    let v = tx.snapshot().get(0, b"key", slice.as_mut()).unwrap();
+    assert_eq!(v.unwrap(), b"value");
+
+    tx.set_snapshot();
+    let v = tx.snapshot().get(0, b"key", slice.as_mut()).unwrap();
+    assert_eq!(v.unwrap(), b"value1");
+    tx.put(0, b"key", b"value2").unwrap();
+    tx.commit().unwrap();
+}
+
+#[test]
+fn test_tx_get_for_update() {
+    let (db, _dir) = open_temp(1);
+    db.put(0, b"key", b"value").unwrap();
+    moveit! {
+        let mut slice = PinnableSlice::new();
+        let write_options = WriteOptions::new();
+        let mut transaction_options = TransactionOptions::new();
+    }
+    transaction_options.lock_timeout = 0;
+
+    let mut tx = db.begin_transaction();
+    let v = tx
+        .get_for_update(0, b"key", true, slice.as_mut())
+        .unwrap()
+        .unwrap();
+    assert_eq!(v, b"value");
+
+    let mut tx1 = db.begin_transaction_with_options(&write_options, &transaction_options);
+    let err = tx1.put(0, b"key", b"value1").unwrap_err();
+    assert!(err.code == Status_Code::kTimedOut);
+
+    tx.commit().unwrap();
+    tx1.put(0, b"key", b"value1").unwrap();
+}
+
 #[test]
 fn test_iter() {
     let (db, _dir) = open_temp(1);
@@ -144,7 +422,81 @@ fn test_iter() {
 
     let mut iter = db.iter(0, Direction::Forward);
     iter.seek(b"key2");
-    assert_eq!(iter.count(), 3);
+    assert_eq!(iter.by_ref().count(), 3);
+    iter.status().unwrap();
+
+    let mut iter = db.iter(0, Direction::Forward);
+    let mut n = 0;
+    while iter.advance() {
+        assert!(iter.key().unwrap().starts_with(b"key"));
+        n += 1;
+    }
+    assert_eq!(n, 5);
+}
+
+#[test]
+fn test_iter_status() {
+    let (db, dir) = open_temp(1);
+    db.put(0, b"key", b"value").unwrap();
+    db.flush(0).unwrap();
+    corrupt_sst(dir.path());
+
+    // ReadOptions::verify_checksums is on by default.
+    let mut iter = db.iter(0, Direction::Forward);
+    assert!(!iter.advance());
+    assert!(!iter.valid());
+    let err = iter.status().unwrap_err();
+    assert!(err.code == Status_Code::kCorruption);
+}
+
+#[test]
+fn test_iter_bound() {
+    let (db, _dir) = open_temp(1);
+    for key in [&b"key"[..], b"key1", b"key3", b"key4", b"key5"] {
+        db.put(0, key, b"value").unwrap();
+    }
+
+    let iter = db.range_iter(0, Direction::Forward, b"key1", b"key4");
+    let keys: Vec<Vec<u8>> = iter.map(|(k, _)| k.into()).collect();
+    assert_eq!(keys, [b"key1".to_vec(), b"key3".to_vec()]);
+
+    let mut iter = db.range_iter(0, Direction::Backward, b"key3", b"key5");
+    let keys: Vec<Vec<u8>> = iter.by_ref().map(|(k, _)| k.into()).collect();
+    assert_eq!(keys, [b"key4".to_vec(), b"key3".to_vec()]);
+    assert!(iter.key().is_none());
+
+    let mut iter = db.range_iter(0, Direction::Forward, b"key1", b"key4");
+    iter.seek(b"key4");
+    assert!(!iter.advance());
+
+    let snapshot = db.snapshot();
+    db.put(0, b"key2", b"value").unwrap();
+    let iter = snapshot.range_iter(0, Direction::Forward, b"key1", b"key4");
+    assert_eq!(iter.count(), 2);
+
+    // The transaction's own writes are outside RocksDB's bound checks.
+    let mut tx = db.begin_transaction();
+    tx.set_snapshot();
+    for key in [&b"key0"[..], b"key2", b"key4", b"key6"] {
+        tx.put(0, key, b"value1").unwrap();
+    }
+    let expected = [b"key1".to_vec(), b"key2".to_vec(), b"key3".to_vec()];
+    let iter = tx.range_iter(0, Direction::Forward, b"key1", b"key4");
+    let keys: Vec<Vec<u8>> = iter.map(|(k, _)| k.into()).collect();
+    assert_eq!(keys, expected);
+    let iter = tx.range_iter(0, Direction::Backward, b"key1", b"key4");
+    let keys: Vec<Vec<u8>> = iter.map(|(k, _)| k.into()).collect();
+    assert_eq!(keys, expected.iter().rev().cloned().collect::<Vec<_>>());
+
+    let snapshot = tx.snapshot();
+    let mut iter = snapshot.range_iter(0, Direction::Backward, b"key1", b"key4");
+    iter.seek_for_prev(b"key9");
+    assert!(iter.advance());
+    assert_eq!(iter.key().unwrap(), b"key3");
+    let mut iter = snapshot.range_iter(0, Direction::Forward, b"key1", b"key4");
+    iter.seek(b"key");
+    assert!(iter.advance());
+    assert_eq!(iter.key().unwrap(), b"key1");
 }
 
 #[test]
@@ -154,7 +506,11 @@ fn test_write_batch() {
     let mut wb = db.new_write_batch();
     wb.put(0, b"key1", b"value1").unwrap();
     wb.delete(0, b"key").unwrap();
+    assert_eq!(wb.len(), 2);
+    assert!(wb.data_size() > 0);
     db.write(&mut wb).unwrap();
+    wb.clear();
+    assert!(wb.is_empty());
     moveit! {
         let mut buf = PinnableSlice::new();
     }
@@ -162,6 +518,34 @@ fn test_write_batch() {
     assert!(db.get(0, b"key1", buf.as_mut()).unwrap().is_some());
 }
 
+#[test]
+fn test_merge() {
+    let dir = tempdir().unwrap();
+    let db = DbOptions::new(dir.path(), 1)
+        .create_if_missing(true)
+        .create_missing_column_families(true)
+        .merge_operator("uint64add")
+        .unwrap()
+        .open()
+        .unwrap();
+    db.merge(0, b"counter", &1u64.to_le_bytes()).unwrap();
+    let mut tx = db.begin_transaction();
+    tx.merge(0, b"counter", &2u64.to_le_bytes()).unwrap();
+    tx.commit().unwrap();
+    let mut wb = db.new_write_batch();
+    wb.merge(0, b"counter", &3u64.to_le_bytes()).unwrap();
+    db.write(&mut wb).unwrap();
+    moveit! {
+        let mut buf = PinnableSlice::new();
+    }
+    let v = db.get(0, b"counter", buf.as_mut()).unwrap().unwrap();
+    assert_eq!(v, 6u64.to_le_bytes());
+
+    assert!(DbOptions::new(dir.path(), 1)
+        .merge_operator("no-such-operator")
+        .is_err());
+}
+
 #[test]
 fn test_clear_cf() {
     let (mut db, _dir) = open_temp(1);
@@ -172,3 +556,50 @@ fn test_clear_cf() {
     db.put(0, b"key", b"value").unwrap();
     assert_eq!(db.iter(0, Direction::Forward).count(), 1);
 }
+
+#[test]
+fn test_tx_elapsed_time() {
+    let (db, _dir) = open_temp(1);
+    let tx = db.begin_transaction();
+    std::thread::sleep(std::time::Duration::from_millis(10));
+    assert!(tx.elapsed_time() >= std::time::Duration::from_millis(10));
+}
+
+#[test]
+fn test_create_cf() {
+    let (mut db, _dir) = open_temp(1);
+    db.put(0, b"key", b"value").unwrap();
+    db.drop_cf(0).unwrap();
+    db.create_cf(0).unwrap();
+    assert_eq!(db.iter(0, Direction::Forward).count(), 0);
+    db.put(0, b"key", b"value").unwrap();
+    assert_eq!(db.iter(0, Direction::Forward).count(), 1);
+
+    assert!(db.create_cf(db.default_col()).is_err());
+}
+
+#[test]
+fn test_create_cf_keeps_options() {
+    let (mut db, _dir) = open_temp(1);
+    db.set_options(0, [("write_buffer_size", "65536")]).unwrap();
+    db.drop_cf(0).unwrap();
+    db.create_cf(0).unwrap();
+
+    // With the default 64MB write buffer all of these would stay in the
+    // active memtable.
+    for i in 0..1000u32 {
+        db.put(0, &i.to_be_bytes(), &[0; 1024]).unwrap();
+    }
+    let entries = db
+        .get_int_property(0, "rocksdb.num-entries-active-mem-table")
+        .unwrap();
+    assert!(entries < 1000);
+}
+
+#[test]
+fn test_latest_sequence_number() {
+    let (db, _dir) = open_temp(1);
+    let seq = db.latest_sequence_number();
+    db.put(0, b"key", b"value").unwrap();
+    assert!(db.latest_sequence_number() > seq);
+}